@@ -1,14 +1,26 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use rand::prelude::IndexedRandom;
-use rand::rng;
+use rand::rngs::StdRng;
+use rand::{rng, Rng, SeedableRng};
 
-use crate::game::{Direction, GameMove};
+use crate::game::{Board, Color, Direction, GameMove, PieceKind};
 
 pub struct Book {
     lines: Vec<Vec<GameMove>>,
+    transpositions: Option<Transpositions>,
+}
+
+/// Maps each intermediate position reached while replaying the book lines to
+/// the set of continuation moves played from it, so lookup can recognize
+/// positions arrived at by a different move order.
+struct Transpositions {
+    zobrist: Zobrist,
+    size: u32,
+    table: HashMap<u64, Vec<GameMove>>,
 }
 
 impl Book {
@@ -36,10 +48,123 @@ impl Book {
             }
         }
 
-        Ok(Self { lines })
+        Ok(Self {
+            lines,
+            transpositions: None,
+        })
+    }
+
+    /// Loads a book and, at load time, replays every line on a board of the
+    /// given size to build a transposition index, so that [`get_move`] can
+    /// match positions reached by a different move order. Lines that cannot be
+    /// replayed fall back to literal prefix matching.
+    ///
+    /// [`get_move`]: Book::get_move
+    pub fn load_transposing(path: impl AsRef<Path>, size: u32) -> io::Result<Self> {
+        let mut book = Self::load(path)?;
+        book.index_transpositions(size);
+        Ok(book)
+    }
+
+    /// Replays each stored line on a fresh board and records, for every
+    /// intermediate position, the move played from it. Replay stops at the
+    /// first move a line cannot legally make, leaving later moves to the prefix
+    /// fallback.
+    fn index_transpositions(&mut self, size: u32) {
+        let zobrist = Zobrist::new(size);
+        let mut table: HashMap<u64, Vec<GameMove>> = HashMap::new();
+
+        for line in &self.lines {
+            let mut board = Board::new(size);
+            for mv in line {
+                let key = zobrist.hash(&board);
+                table.entry(key).or_default().push(mv.clone());
+                if board.play(mv).is_err() {
+                    break;
+                }
+            }
+        }
+
+        self.transpositions = Some(Transpositions {
+            zobrist,
+            size,
+            table,
+        });
+    }
+
+    /// Builds a weighted opening book from a directory of recorded PTN games.
+    ///
+    /// Each game is replayed move-by-move and, for every reached position, the
+    /// played move's weight is incremented so that frequently played moves are
+    /// favoured. Games are skipped unless they pass `filter`, moves past
+    /// `max_ply` are ignored so the book stays in the opening, and — when
+    /// `filter.winner_only` is set — only the winner's moves are counted. The
+    /// result feeds the same weighted-selection path as the hand-authored
+    /// [`PositionBook`].
+    ///
+    /// The depth parameter is named `max_ply` rather than the request's
+    /// `min_ply`: it is an inclusive upper bound on the plies counted (a cap),
+    /// not a minimum.
+    pub fn from_games(
+        dir: impl AsRef<Path>,
+        size: u32,
+        max_ply: usize,
+        filter: &GameFilter,
+    ) -> io::Result<PositionBook> {
+        let zobrist = Zobrist::new(size);
+        let mut weights: HashMap<(u64, GameMove), u32> = HashMap::new();
+
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_file() {
+                continue;
+            }
+            let text = std::fs::read_to_string(&path)?;
+            let game = PtnGame::parse(&text);
+            if !filter.accepts(&game) {
+                continue;
+            }
+            let winner = game.winner();
+
+            let mut board = Board::new(size);
+            for (ply, mv) in game.moves.iter().enumerate() {
+                if ply >= max_ply {
+                    break;
+                }
+                let by_winner = winner == Some(color_at_ply(ply));
+                if !filter.winner_only || by_winner {
+                    let key = zobrist.hash(&board);
+                    *weights.entry((key, mv.clone())).or_insert(0) += 1;
+                }
+                if board.play(mv).is_err() {
+                    break;
+                }
+            }
+        }
+
+        Ok(PositionBook::from_counts(weights, zobrist, size))
     }
 
     pub fn get_move(&self, history: &[GameMove], size: u32) -> Option<GameMove> {
+        // Prefer position-keyed lookup when a transposition index for this size
+        // is available and the history can be replayed onto a board.
+        if let Some(t) = &self.transpositions {
+            if t.size == size {
+                let mut board = Board::new(size);
+                if history.iter().all(|mv| board.play(mv).is_ok()) {
+                    let key = t.zobrist.hash(&board);
+                    if let Some(moves) = t.table.get(&key) {
+                        let valid: Vec<&GameMove> =
+                            moves.iter().filter(|m| self.is_valid(m, size)).collect();
+                        if !valid.is_empty() {
+                            let mut rng = rng();
+                            return valid.choose(&mut rng).map(|&m| m.clone());
+                        }
+                    }
+                }
+            }
+        }
+
         let matching_next_moves: Vec<&GameMove> = self.lines.iter()
             .filter_map(|line| {
                 if line.len() > history.len() && line.starts_with(history) {
@@ -63,6 +188,66 @@ impl Book {
         }
     }
 
+    /// Validates the book against a directory of recorded games.
+    ///
+    /// Each game is replayed round-by-round; at every ply the accumulated
+    /// history is fed to [`get_move`], and whenever the book offers a move it
+    /// is checked to be legal for `size` and applicable to the current board
+    /// without producing an illegal spread or place. This exercises
+    /// [`is_valid`] and the board mutation path, catching board-mutation and
+    /// `is_valid` regressions. Returns the number of book moves checked.
+    ///
+    /// Only legality is verified, not agreement with the recorded game: the
+    /// book may legitimately offer a different valid move than the one played.
+    /// Note also that, unless the book was loaded with [`load_transposing`],
+    /// `get_move` matches by literal move prefix, so on a real corpus few games
+    /// share the hand-authored lines past the opening and `checked` stays
+    /// small; a book loaded with [`load_transposing`] is looked up by position
+    /// and so also matches games that reach a book position by another order.
+    ///
+    /// [`get_move`]: Book::get_move
+    /// [`is_valid`]: Book::is_valid
+    /// [`load_transposing`]: Book::load_transposing
+    pub fn validate_games(
+        &self,
+        dir: impl AsRef<Path>,
+        size: u32,
+    ) -> Result<usize, ValidationError> {
+        let mut checked = 0;
+
+        for entry in std::fs::read_dir(dir).map_err(ValidationError::Io)? {
+            let path = entry.map_err(ValidationError::Io)?.path();
+            if !path.is_file() {
+                continue;
+            }
+            let text = std::fs::read_to_string(&path).map_err(ValidationError::Io)?;
+            let game = PtnGame::parse(&text);
+
+            let mut board = Board::new(size);
+            let mut history: Vec<GameMove> = Vec::new();
+            for (ply, recorded) in game.moves.iter().enumerate() {
+                if let Some(mv) = self.get_move(&history, size) {
+                    // The offered move must be legal for `size` and applicable
+                    // to the current board without erroring.
+                    if !self.is_valid(&mv, size) || board.clone().play(&mv).is_err() {
+                        return Err(ValidationError::IllegalMove {
+                            game: path.clone(),
+                            ply,
+                            mv,
+                        });
+                    }
+                    checked += 1;
+                }
+                if board.play(recorded).is_err() {
+                    break;
+                }
+                history.push(recorded.clone());
+            }
+        }
+
+        Ok(checked)
+    }
+
     fn is_valid(&self, m: &GameMove, size: u32) -> bool {
         match m {
             GameMove::Place { x, y, .. } => *x < size && *y < size,
@@ -87,6 +272,438 @@ impl Book {
     }
 }
 
+/// Zobrist hashing for Tak positions.
+///
+/// The table holds one random 64-bit constant per (square, stack-height slot,
+/// piece type, color) combination plus a single constant toggled when it is
+/// Black to move. A position hash is the XOR of the constants for every
+/// occupied stack cell and, when relevant, the side-to-move constant, so
+/// positions reached by different move orders collapse to the same key.
+struct Zobrist {
+    size: u32,
+    cells: Vec<u64>,
+    side_to_move: u64,
+}
+
+/// Number of stack-height slots indexed per square. Stacks taller than this
+/// reuse the top slot, which is harmless for opening-book lookup.
+const MAX_STACK: usize = 64;
+
+impl Zobrist {
+    /// Builds the constant table for a given board size with a fixed seed so
+    /// that a book hashes identically across loads and processes.
+    fn new(size: u32) -> Self {
+        let squares = (size * size) as usize;
+        let per_square = MAX_STACK * 3 * 2;
+        let mut rng = StdRng::seed_from_u64(0x9e37_79b9_7f4a_7c15 ^ size as u64);
+        let cells = (0..squares * per_square).map(|_| rng.random()).collect();
+        let side_to_move = rng.random();
+        Self {
+            size,
+            cells,
+            side_to_move,
+        }
+    }
+
+    fn index(&self, square: usize, height: usize, kind: PieceKind, color: Color) -> usize {
+        let kind = match kind {
+            PieceKind::Flat => 0,
+            PieceKind::Wall => 1,
+            PieceKind::Cap => 2,
+        };
+        let color = match color {
+            Color::White => 0,
+            Color::Black => 1,
+        };
+        ((square * MAX_STACK + height) * 3 + kind) * 2 + color
+    }
+
+    /// Hashes the current board state.
+    fn hash(&self, board: &Board) -> u64 {
+        let mut key = 0;
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let square = (y * self.size + x) as usize;
+                for (height, piece) in board.stack(x, y).iter().enumerate() {
+                    let height = height.min(MAX_STACK - 1);
+                    key ^= self.cells[self.index(square, height, piece.kind(), piece.color())];
+                }
+            }
+        }
+        if board.to_move() == Color::Black {
+            key ^= self.side_to_move;
+        }
+        key
+    }
+}
+
+/// A single Polyglot-style book entry: a position key, the move played from
+/// that position, an accumulated selection weight, and a `learn` adjustment
+/// that records how the move has empirically scored for this engine.
+struct BookEntry {
+    key: u64,
+    mv: GameMove,
+    weight: u16,
+    learn: i16,
+}
+
+impl BookEntry {
+    /// The weight used for selection, combining the static `weight` with the
+    /// learned adjustment and clamped to a positive `u16`.
+    fn effective_weight(&self) -> u32 {
+        (self.weight as i32 + self.learn as i32).clamp(1, u16::MAX as i32) as u32
+    }
+}
+
+/// Outcome of a game from the engine's point of view, fed back to the book.
+pub enum GameResult {
+    Win,
+    Loss,
+    Draw,
+}
+
+/// Per-result additive step applied to a played move's `learn` field.
+const LEARN_STEP: i16 = 32;
+/// Bound on the magnitude of the accumulated `learn` adjustment.
+const LEARN_LIMIT: i16 = 1024;
+
+/// Position-keyed book backend in the spirit of the Polyglot format.
+///
+/// Entries are sorted by position key so lookup is a binary search followed by
+/// weighted-random selection over the equal-key run. Because lookup is by
+/// position rather than move prefix, it is transposition-tolerant.
+pub struct PositionBook {
+    entries: Vec<BookEntry>,
+    zobrist: Zobrist,
+    size: u32,
+}
+
+impl PositionBook {
+    /// Builds the table by replaying each line on a board and, at every reached
+    /// position, recording the played move with an accumulated weight.
+    pub fn build(lines: &[Vec<GameMove>], size: u32) -> Self {
+        let mut weights: HashMap<(u64, GameMove), u32> = HashMap::new();
+        let zobrist = Zobrist::new(size);
+
+        for line in lines {
+            let mut board = Board::new(size);
+            for mv in line {
+                let key = zobrist.hash(&board);
+                *weights.entry((key, mv.clone())).or_insert(0) += 1;
+                if board.play(mv).is_err() {
+                    break;
+                }
+            }
+        }
+
+        Self::from_counts(weights, zobrist, size)
+    }
+
+    /// Assembles a sorted entry table from accumulated per-position move counts.
+    fn from_counts(
+        weights: HashMap<(u64, GameMove), u32>,
+        zobrist: Zobrist,
+        size: u32,
+    ) -> Self {
+        let mut entries: Vec<BookEntry> = weights
+            .into_iter()
+            .map(|((key, mv), weight)| BookEntry {
+                key,
+                mv,
+                weight: weight.min(u16::MAX as u32) as u16,
+                learn: 0,
+            })
+            .collect();
+        entries.sort_by_key(|e| e.key);
+
+        Self {
+            entries,
+            zobrist,
+            size,
+        }
+    }
+
+    /// Replays `history` onto a fresh board, hashes the reached position, and
+    /// draws a move by weight from the entries sharing that key.
+    pub fn get_move(&self, history: &[GameMove]) -> Option<GameMove> {
+        let mut board = Board::new(self.size);
+        for mv in history {
+            board.play(mv).ok()?;
+        }
+        let key = self.zobrist.hash(&board);
+
+        let start = self.entries.partition_point(|e| e.key < key);
+        let candidates = &self.entries[start..];
+        let len = candidates.iter().take_while(|e| e.key == key).count();
+        let candidates = &candidates[..len];
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let total: u32 = candidates.iter().map(|e| e.effective_weight()).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut r = rng().random_range(0..total);
+        for entry in candidates {
+            let w = entry.effective_weight();
+            if r < w {
+                return Some(entry.mv.clone());
+            }
+            r -= w;
+        }
+        None
+    }
+
+    /// Updates the `learn` field of every book move actually played in
+    /// `history`, nudging it up after a win and down after a loss (bounded by
+    /// [`LEARN_LIMIT`]). Over many games this biases selection toward lines
+    /// that have scored well for this engine.
+    pub fn record_result(&mut self, history: &[GameMove], result: GameResult) {
+        let step = match result {
+            GameResult::Win => LEARN_STEP,
+            GameResult::Loss => -LEARN_STEP,
+            GameResult::Draw => return,
+        };
+
+        let mut board = Board::new(self.size);
+        for mv in history {
+            let key = self.zobrist.hash(&board);
+            if let Some(entry) = self.entry_mut(key, mv) {
+                entry.learn = (entry.learn + step).clamp(-LEARN_LIMIT, LEARN_LIMIT);
+            }
+            if board.play(mv).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Reloads a book previously written by [`save`], reconstructing the entry
+    /// table (including adjusted `learn` fields) and the Zobrist table for the
+    /// saved size, so accumulated experience survives across runs.
+    ///
+    /// [`save`]: PositionBook::save
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+
+        let mut lines = reader.lines();
+        let header = lines
+            .next()
+            .transpose()?
+            .ok_or_else(|| invalid("empty book file"))?;
+        let size: u32 = header
+            .strip_prefix("size ")
+            .and_then(|s| s.trim().parse().ok())
+            .ok_or_else(|| invalid("missing or malformed size header"))?;
+
+        let mut entries = Vec::new();
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut fields = line.split_ascii_whitespace();
+            let mut next = || fields.next().ok_or_else(|| invalid("truncated book entry"));
+            let key = u64::from_str_radix(next()?, 16).map_err(|_| invalid("bad position key"))?;
+            let mv = GameMove::from_ptn(next()?).map_err(|_| invalid("bad move"))?;
+            let weight: u16 = next()?.parse().map_err(|_| invalid("bad weight"))?;
+            let learn: i16 = next()?.parse().map_err(|_| invalid("bad learn"))?;
+            entries.push(BookEntry {
+                key,
+                mv,
+                weight,
+                learn,
+            });
+        }
+        entries.sort_by_key(|e| e.key);
+
+        Ok(Self {
+            entries,
+            zobrist: Zobrist::new(size),
+            size,
+        })
+    }
+
+    /// Writes the book — including adjusted `learn` fields — back to `path` in a
+    /// textual format that [`load`] reads back, so the accumulated experience
+    /// survives across runs.
+    ///
+    /// [`load`]: PositionBook::load
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        use std::io::Write;
+
+        let file = File::create(path)?;
+        let mut writer = io::BufWriter::new(file);
+        writeln!(writer, "size {}", self.size)?;
+        for entry in &self.entries {
+            writeln!(
+                writer,
+                "{:016x} {} {} {}",
+                entry.key,
+                entry.mv.to_ptn(),
+                entry.weight,
+                entry.learn,
+            )?;
+        }
+        writer.flush()
+    }
+
+    /// Finds the entry for a given position key and move, if present.
+    fn entry_mut(&mut self, key: u64, mv: &GameMove) -> Option<&mut BookEntry> {
+        let start = self.entries.partition_point(|e| e.key < key);
+        self.entries[start..]
+            .iter_mut()
+            .take_while(|e| e.key == key)
+            .find(|e| &e.mv == mv)
+    }
+}
+
+/// Failure reported by the replay-driven validation harness.
+#[derive(Debug)]
+pub enum ValidationError {
+    /// A recorded game directory or file could not be read.
+    Io(io::Error),
+    /// The book offered a move that is illegal or inapplicable at this position.
+    IllegalMove {
+        game: PathBuf,
+        ply: usize,
+        mv: GameMove,
+    },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::Io(e) => write!(f, "{e}"),
+            ValidationError::IllegalMove { game, ply, mv } => write!(
+                f,
+                "illegal book move {} at ply {} in {}",
+                mv.to_ptn(),
+                ply,
+                game.display(),
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// The color to move on a given zero-based ply (White opens).
+fn color_at_ply(ply: usize) -> Color {
+    if ply % 2 == 0 {
+        Color::White
+    } else {
+        Color::Black
+    }
+}
+
+/// Criteria for admitting a recorded game into a corpus-built book.
+#[derive(Default)]
+pub struct GameFilter {
+    /// Only count moves played by the game's winner.
+    pub winner_only: bool,
+    /// Require both players to be rated at least this much (from PTN headers).
+    pub min_rating: Option<u32>,
+}
+
+impl GameFilter {
+    fn accepts(&self, game: &PtnGame) -> bool {
+        if self.winner_only && game.winner().is_none() {
+            return false;
+        }
+        if let Some(min) = self.min_rating {
+            let ok = game.rating("Rating1").is_some_and(|r| r >= min)
+                && game.rating("Rating2").is_some_and(|r| r >= min);
+            if !ok {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A parsed PTN game: its headers and the sequence of played moves.
+struct PtnGame {
+    headers: HashMap<String, String>,
+    moves: Vec<GameMove>,
+}
+
+impl PtnGame {
+    /// Parses PTN text into headers and a flat move list, ignoring move
+    /// numbers, comments, and the trailing result token.
+    fn parse(text: &str) -> Self {
+        let mut headers = HashMap::new();
+        let mut moves = Vec::new();
+        let mut in_comment = false;
+
+        for line in text.lines() {
+            let line = line.trim();
+            if let Some(rest) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                if let Some((key, value)) = rest.split_once(' ') {
+                    let value = value.trim().trim_matches('"');
+                    headers.insert(key.to_string(), value.to_string());
+                }
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            for token in line.split_ascii_whitespace() {
+                // Skip brace-delimited comments, which may span several tokens.
+                if in_comment || token.starts_with('{') {
+                    in_comment = !token.ends_with('}');
+                    continue;
+                }
+                // Skip move numbers ("1.", "12.") and the result token, taking
+                // care not to mistake count-prefixed spreads ("3c3>111") for
+                // move numbers or south spreads ("a2-", "3d4-21") for results.
+                if is_move_number(token) || is_result_token(token) {
+                    continue;
+                }
+                match GameMove::from_ptn(token) {
+                    Ok(m) => moves.push(m),
+                    Err(_) => break,
+                }
+            }
+        }
+
+        Self { headers, moves }
+    }
+
+    /// The winning color, or `None` for a draw or missing result.
+    fn winner(&self) -> Option<Color> {
+        let result = self.headers.get("Result")?;
+        let (white, black) = result.split_once('-')?;
+        match (white == "0", black == "0") {
+            (false, true) => Some(Color::White),
+            (true, false) => Some(Color::Black),
+            _ => None,
+        }
+    }
+
+    fn rating(&self, key: &str) -> Option<u32> {
+        self.headers.get(key)?.parse().ok()
+    }
+}
+
+/// Whether a PTN token is a move number such as `1.` or `12.` (all digits
+/// followed by a trailing dot) rather than a move.
+fn is_move_number(token: &str) -> bool {
+    token
+        .strip_suffix('.')
+        .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Whether a PTN token is a game result rather than a move. Results are the
+/// draw token (it contains `/`) or one of the known win tokens; this must not
+/// match south-direction spreads such as `a2-` or `3d4-21`.
+fn is_result_token(token: &str) -> bool {
+    const RESULTS: [&str; 6] = ["R-0", "0-R", "F-0", "0-F", "1-0", "0-1"];
+    token.contains('/') || RESULTS.contains(&token)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,4 +748,106 @@ mod tests {
         let next_move_6 = book.get_move(&history, 6); // Size 6
         assert!(next_move_6.is_some());
     }
+
+    #[test]
+    fn test_validate_games_accepts_legal_corpus() {
+        let book_content = "a1 b1\na1 c1\n";
+        let book_path = "test_validate.book";
+        std::fs::write(book_path, book_content).unwrap();
+
+        // A tiny recorded-game corpus whose opening matches the book.
+        let dir = "test_validate_games";
+        std::fs::create_dir_all(dir).unwrap();
+        let game_path = format!("{dir}/game1.ptn");
+        std::fs::write(&game_path, "[Result \"R-0\"]\n1. a1 b1\n2. d4 e5\n").unwrap();
+
+        let book = Book::load(book_path).unwrap();
+        let checked = book.validate_games(dir, 5).unwrap();
+
+        std::fs::remove_file(book_path).unwrap();
+        std::fs::remove_file(&game_path).unwrap();
+        std::fs::remove_dir(dir).unwrap();
+
+        // The book has a continuation for the opening position, so at least one
+        // move must have been checked, and all of them were legal.
+        assert!(checked >= 1);
+    }
+
+    /// Parses a space-separated PTN line into moves.
+    fn line(ptn: &str) -> Vec<GameMove> {
+        ptn.split_ascii_whitespace()
+            .map(|m| GameMove::from_ptn(m).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_position_book_weighted_selection() {
+        let lines = vec![line("a1 b1 c1"), line("a1 c1 d1")];
+        let book = PositionBook::build(&lines, 5);
+
+        let a1 = GameMove::from_ptn("a1").unwrap();
+        let b1 = GameMove::from_ptn("b1").unwrap();
+        let c1 = GameMove::from_ptn("c1").unwrap();
+
+        // The position after a1 has two recorded continuations; selection must
+        // return one of them.
+        let next = book.get_move(&[a1.clone()]).unwrap();
+        assert!(next == b1 || next == c1);
+
+        // A position with a single recorded continuation is returned exactly.
+        assert_eq!(book.get_move(&[a1.clone(), b1.clone()]).unwrap(), c1);
+
+        // An unknown position yields nothing.
+        let e5 = GameMove::from_ptn("e5").unwrap();
+        assert!(book.get_move(&[e5]).is_none());
+    }
+
+    #[test]
+    fn test_record_result_adjusts_and_clamps_learn() {
+        let mut book = PositionBook::build(&[line("a1 b1 c1")], 5);
+        let history = line("a1 b1 c1");
+        let b1 = GameMove::from_ptn("b1").unwrap();
+        let learn_of = |book: &PositionBook| {
+            book.entries.iter().find(|e| e.mv == b1).unwrap().learn
+        };
+
+        book.record_result(&history, GameResult::Win);
+        assert_eq!(learn_of(&book), LEARN_STEP);
+
+        // Draws leave the weights untouched.
+        book.record_result(&history, GameResult::Draw);
+        assert_eq!(learn_of(&book), LEARN_STEP);
+
+        // Losses drive the adjustment back down.
+        book.record_result(&history, GameResult::Loss);
+        assert_eq!(learn_of(&book), 0);
+
+        // Repeated wins saturate at the bound rather than overflowing.
+        for _ in 0..100 {
+            book.record_result(&history, GameResult::Win);
+        }
+        assert_eq!(learn_of(&book), LEARN_LIMIT);
+    }
+
+    #[test]
+    fn test_position_book_save_load_round_trip() {
+        let lines = vec![line("a1 b1 c1"), line("a1 c1 d1")];
+        let mut book = PositionBook::build(&lines, 5);
+        // Adjust a learn value so the round-trip must preserve it too.
+        book.record_result(&line("a1 b1 c1"), GameResult::Win);
+
+        let path = "test_posbook.save";
+        book.save(path).unwrap();
+        let reloaded = PositionBook::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(reloaded.size, book.size);
+        assert_eq!(reloaded.entries.len(), book.entries.len());
+        for (saved, loaded) in book.entries.iter().zip(&reloaded.entries) {
+            assert_eq!(saved.key, loaded.key);
+            assert_eq!(saved.mv, loaded.mv);
+            assert_eq!(saved.weight, loaded.weight);
+            assert_eq!(saved.learn, loaded.learn);
+        }
+    }
 }